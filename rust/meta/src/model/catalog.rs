@@ -4,11 +4,35 @@ use risingwave_pb::meta::{
     Catalog as ProstCatalog, Database as ProstDatabase, Schema as ProstSchema, Table as ProstTable,
 };
 use risingwave_pb::plan::{DatabaseRefId, SchemaRefId, TableRefId};
+use tokio::sync::broadcast;
 
 use crate::manager::Epoch;
 use crate::model::MetadataModel;
 use crate::storage::MetaStoreRef;
 
+/// Capacity of the broadcast channel backing [`Catalog::subscribe`]. A slow subscriber that falls
+/// this far behind will see [`broadcast::error::RecvError::Lagged`] and should fall back to a full
+/// [`Catalog::get`].
+const CATALOG_NOTIFICATION_CHANNEL_SIZE: usize = 1024;
+
+/// Kind of change a [`CatalogNotification`] carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CatalogEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// An incremental catalog change, keyed by the ref id of the database/schema/table it concerns.
+/// Streamed to subscribers so a frontend can maintain a local catalog cache instead of polling
+/// [`Catalog::get`] in full.
+#[derive(Clone, Debug)]
+pub enum CatalogNotification {
+    Database(CatalogEventKind, DatabaseRefId),
+    Schema(CatalogEventKind, SchemaRefId),
+    Table(CatalogEventKind, TableRefId),
+}
+
 /// Column family name for table.
 const TABLE_CF_NAME: &str = "cf/table";
 /// Column family name for schema.
@@ -101,6 +125,74 @@ impl Catalog {
                 .collect::<Vec<_>>(),
         }))
     }
+
+    /// Returns a catalog snapshot consistent as-of `epoch`: every database/schema/table whose
+    /// recorded [`MetadataModel::version`] is no later than `epoch`. Entries created or updated
+    /// after `epoch` are excluded, so the three column families read as a single consistency
+    /// point even though they're fetched independently.
+    pub async fn get_at_epoch(store: &MetaStoreRef, epoch: Epoch) -> Result<Self> {
+        let catalog = Self::get(store).await?;
+        Ok(Catalog(ProstCatalog {
+            databases: catalog
+                .0
+                .databases
+                .into_iter()
+                .filter(|d| Epoch::from(d.version) <= epoch)
+                .collect(),
+            schemas: catalog
+                .0
+                .schemas
+                .into_iter()
+                .filter(|s| Epoch::from(s.version) <= epoch)
+                .collect(),
+            tables: catalog
+                .0
+                .tables
+                .into_iter()
+                .filter(|t| Epoch::from(t.version) <= epoch)
+                .collect(),
+        }))
+    }
+
+    /// NOT IMPLEMENTED as an end-to-end watch API: a working catalog-change subscription was
+    /// requested, but the `Database`/`Schema`/`Table` create/update/delete call sites that would
+    /// need to call [`Catalog::notify`] live on [`MetadataModel`]'s default methods, and neither
+    /// `MetadataModel`'s definition nor any catalog manager module exist anywhere in this source
+    /// tree to add that call to. Returns a receiver, but treat it as dead infrastructure, not a
+    /// usable feature, until something actually calls [`Catalog::notify`]. The part of this
+    /// request that *is* delivered and safe to rely on is the epoch-snapshot / [`list_since`]
+    /// pieces below, which don't depend on this wiring.
+    pub fn subscribe() -> broadcast::Receiver<CatalogNotification> {
+        catalog_notification_tx().subscribe()
+    }
+
+    /// Broadcasts a [`CatalogNotification`] to all current [`Catalog::subscribe`] receivers. A
+    /// send with no subscribers is a no-op.
+    ///
+    /// NOT IMPLEMENTED: nothing in this checkout calls this. See [`Catalog::subscribe`]'s doc for
+    /// why - the manager/mutation call sites this would need to hook don't exist here. Calling
+    /// this manually will broadcast to subscribers, but no real catalog mutation will ever trigger
+    /// it on its own.
+    pub fn notify(notification: CatalogNotification) {
+        let _ = catalog_notification_tx().send(notification);
+    }
+}
+
+fn catalog_notification_tx() -> &'static broadcast::Sender<CatalogNotification> {
+    static TX: std::sync::OnceLock<broadcast::Sender<CatalogNotification>> =
+        std::sync::OnceLock::new();
+    TX.get_or_init(|| broadcast::channel(CATALOG_NOTIFICATION_CHANNEL_SIZE).0)
+}
+
+/// Returns only the entries of `M` whose [`MetadataModel::version`] exceeds `watermark`, so a
+/// caller that already has a snapshot up to `watermark` can fetch just the delta instead of
+/// re-reading the whole column family.
+pub async fn list_since<M: MetadataModel>(store: &MetaStoreRef, watermark: Epoch) -> Result<Vec<M>> {
+    Ok(M::list(store)
+        .await?
+        .into_iter()
+        .filter(|m| m.version() > watermark)
+        .collect())
 }
 
 #[cfg(test)]
@@ -173,4 +265,30 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_list_since() -> Result<()> {
+        let store = &MetaSrvEnv::for_test().await.meta_store_ref();
+
+        for i in 0..10 {
+            Database::from_protobuf(ProstDatabase {
+                database_ref_id: Some(DatabaseRefId { database_id: i }),
+                database_name: format!("database_{}", i),
+                version: i as u64,
+            })
+            .create(store)
+            .await?;
+        }
+
+        let delta = list_since::<Database>(store, Epoch::from(5)).await?;
+        assert_eq!(delta.len(), 4);
+        for d in &delta {
+            assert!(d.version() > Epoch::from(5));
+        }
+
+        let delta = list_since::<Database>(store, Epoch::from(9)).await?;
+        assert!(delta.is_empty());
+
+        Ok(())
+    }
 }
@@ -2,20 +2,74 @@ use super::{Array, ArrayBuilder, ArrayIterator, NULL_VAL_FOR_HASH};
 
 use crate::buffer::Bitmap;
 
-use crate::error::Result;
+use crate::error::{ErrorCode, Result};
 
 use crate::types::NativeType;
 
 use crate::array2::ArrayImpl;
+use chrono::{NaiveDateTime, TimeZone, Utc};
 use risingwave_proto::data::{Buffer as BufferProto, Buffer, Buffer_CompressionType};
 use std::fmt::Debug;
+use std::str::FromStr;
 use std::{
     hash::{Hash, Hasher},
     mem::size_of,
 };
 
+/// Buffers smaller than this, in bytes, skip compression: the codec framing overhead isn't worth
+/// paying for a handful of values.
+const COMPRESSION_MIN_SIZE: usize = 4096;
+
+/// Codec used to compress a `Buffer`'s body before it goes on the wire. Chosen per-buffer in
+/// [`PrimitiveArray::to_protobuf`] based on [`COMPRESSION_MIN_SIZE`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompressionCodec {
+    None,
+    Lz4,
+    Zstd,
+}
+
+impl CompressionCodec {
+    fn to_proto(self) -> Buffer_CompressionType {
+        match self {
+            CompressionCodec::None => Buffer_CompressionType::NONE,
+            CompressionCodec::Lz4 => Buffer_CompressionType::LZ4,
+            CompressionCodec::Zstd => Buffer_CompressionType::ZSTD,
+        }
+    }
+
+    fn from_proto(compression: Buffer_CompressionType) -> Result<Self> {
+        match compression {
+            Buffer_CompressionType::NONE => Ok(CompressionCodec::None),
+            Buffer_CompressionType::LZ4 => Ok(CompressionCodec::Lz4),
+            Buffer_CompressionType::ZSTD => Ok(CompressionCodec::Zstd),
+        }
+    }
+
+    fn compress(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            CompressionCodec::None => data.to_vec(),
+            // Prepends the uncompressed length so decompression doesn't need to be told the
+            // original size out-of-band (e.g. derived from a non-uniform item size like
+            // `Decimal`'s).
+            CompressionCodec::Lz4 => lz4_flex::compress_prepend_size(data),
+            CompressionCodec::Zstd => zstd::stream::encode_all(data, 0)
+                .expect("zstd compression should never fail on an in-memory buffer"),
+        }
+    }
+
+    fn decompress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            CompressionCodec::None => Ok(data.to_vec()),
+            CompressionCodec::Lz4 => lz4_flex::decompress_size_prepended(data)
+                .map_err(|e| ErrorCode::InternalError(format!("lz4 decompress failed: {}", e)).into()),
+            CompressionCodec::Zstd => zstd::stream::decode_all(data)
+                .map_err(|e| ErrorCode::InternalError(format!("zstd decompress failed: {}", e)).into()),
+        }
+    }
+}
+
 /// Physical type of array items. It differs from NativeType with more limited type set.
-/// Specifically, it doesn't support u8/u16/u32/u64.
 pub trait PrimitiveArrayItemType: NativeType {
     /// A helper to convert a primitive array to ArrayImpl.
     fn erase_array_type(arr: PrimitiveArray<Self>) -> ArrayImpl;
@@ -46,6 +100,68 @@ impl PrimitiveArrayItemType for f64 {
         ArrayImpl::Float64(arr)
     }
 }
+impl PrimitiveArrayItemType for u16 {
+    fn erase_array_type(arr: PrimitiveArray<Self>) -> ArrayImpl {
+        ArrayImpl::UInt16(arr)
+    }
+}
+impl PrimitiveArrayItemType for u32 {
+    fn erase_array_type(arr: PrimitiveArray<Self>) -> ArrayImpl {
+        ArrayImpl::UInt32(arr)
+    }
+}
+impl PrimitiveArrayItemType for u64 {
+    fn erase_array_type(arr: PrimitiveArray<Self>) -> ArrayImpl {
+        ArrayImpl::UInt64(arr)
+    }
+}
+impl PrimitiveArrayItemType for Decimal {
+    fn erase_array_type(arr: PrimitiveArray<Self>) -> ArrayImpl {
+        ArrayImpl::Decimal(arr)
+    }
+}
+
+/// A fixed-precision decimal: `value * 10^-scale`, e.g. `{ value: 1050, scale: 2 }` is `10.50`.
+/// Lets sources carry unsigned counters or fixed-point money values without the lossy float
+/// round-trip a bare `f64` would force.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal {
+    pub value: i128,
+    pub scale: u32,
+}
+
+impl Decimal {
+    pub fn new(value: i128, scale: u32) -> Self {
+        Self { value, scale }
+    }
+}
+
+impl NativeType for Decimal {
+    fn to_protobuf(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.value.to_be_bytes());
+        output.extend_from_slice(&self.scale.to_be_bytes());
+    }
+
+    fn from_protobuf(cursor: &mut impl std::io::Read) -> Result<Self> {
+        let mut value_buf = [0u8; 16];
+        cursor
+            .read_exact(&mut value_buf)
+            .map_err(|e| ErrorCode::InternalError(format!("failed to read decimal value: {}", e)))?;
+        let mut scale_buf = [0u8; 4];
+        cursor
+            .read_exact(&mut scale_buf)
+            .map_err(|e| ErrorCode::InternalError(format!("failed to read decimal scale: {}", e)))?;
+        Ok(Decimal {
+            value: i128::from_be_bytes(value_buf),
+            scale: u32::from_be_bytes(scale_buf),
+        })
+    }
+
+    fn hash_wrapper<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+        self.scale.hash(state);
+    }
+}
 
 /// `PrimitiveArray` is a collection of primitive types, such as `i32`, `f32`.
 #[derive(Debug)]
@@ -62,6 +178,28 @@ impl<T: PrimitiveArrayItemType> PrimitiveArray<T> {
         }
         builder.finish()
     }
+
+    /// Reconstructs a `PrimitiveArray` from its serialized `Buffer`, inverting
+    /// [`Array::to_protobuf`]. `cardinality` and `bitmap` come from the enclosing array chunk,
+    /// since `Buffer` only carries the (possibly compressed) value bytes.
+    pub fn from_protobuf(buf: &Buffer, cardinality: usize, bitmap: Bitmap) -> Result<Self> {
+        let codec = CompressionCodec::from_proto(buf.get_compression())?;
+        let body = codec.decompress(buf.get_body())?;
+        let mut cursor = body.as_slice();
+        let mut data = Vec::with_capacity(cardinality);
+        {
+            // `to_protobuf` writes only the non-null values, so a null slot consumes no bytes.
+            let mut valid = bitmap.iter();
+            for _ in 0..cardinality {
+                if valid.next().unwrap_or(false) {
+                    data.push(T::from_protobuf(&mut cursor)?);
+                } else {
+                    data.push(T::default());
+                }
+            }
+        }
+        Ok(PrimitiveArray { bitmap, data })
+    }
 }
 
 impl<T: PrimitiveArrayItemType> Array for PrimitiveArray<T> {
@@ -94,9 +232,15 @@ impl<T: PrimitiveArrayItemType> Array for PrimitiveArray<T> {
                 v.map(|node| node.to_protobuf(&mut output_buffer));
             }
 
+            let codec = if output_buffer.len() >= COMPRESSION_MIN_SIZE {
+                CompressionCodec::Lz4
+            } else {
+                CompressionCodec::None
+            };
+
             let mut b = BufferProto::new();
-            b.set_compression(Buffer_CompressionType::NONE);
-            b.set_body(output_buffer);
+            b.set_compression(codec.to_proto());
+            b.set_body(codec.compress(&output_buffer));
             b
         };
         Ok(vec![values])
@@ -160,6 +304,179 @@ impl<T: PrimitiveArrayItemType> ArrayBuilder for PrimitiveArrayBuilder<T> {
     }
 }
 
+/// Describes how a raw bytes/UTF-8 source column should be coerced into a typed array, so
+/// decoders can declare a per-column cast by name instead of going through a full expression.
+///
+/// Parsed from a conversion string such as `"int"`, `"bool"`, or a format-bearing timestamp spec
+/// `"timestamp|<chrono fmt>"` (optionally `"timestamp+<tz>|<chrono fmt>"` to anchor the input to
+/// a fixed timezone before normalizing to UTC).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conversion {
+    /// `"bytes"` / `"string"` / `"asis"`: no parsing, identity cast.
+    Identity,
+    /// `"int"` / `"integer"`: parse as a signed integer.
+    Int,
+    /// `"float"`: parse as `f64`.
+    Float,
+    /// `"bool"` / `"boolean"`: parse as a boolean, encoded as `0`/`1` (see [`Conversion::cast`]).
+    Bool,
+    /// `"timestamp"`: parse the value as a bare epoch timestamp, in seconds (see
+    /// [`Conversion::cast`] for why this comes back as `Int64` rather than a timestamp array).
+    Timestamp,
+    /// `"timestamp|<fmt>"` / `"timestamp+<tz>|<fmt>"`: parse with a chrono format string,
+    /// optionally anchoring to a fixed timezone before converting to UTC.
+    TimestampFormat { tz: Option<String>, format: String },
+}
+
+impl FromStr for Conversion {
+    type Err = crate::error::RwError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some((head, format)) = s.split_once('|') {
+            let (kind, tz) = match head.split_once('+') {
+                Some((kind, tz)) => (kind, Some(tz.to_string())),
+                None => (head, None),
+            };
+            return if kind == "timestamp" {
+                Ok(Conversion::TimestampFormat {
+                    tz,
+                    format: format.to_string(),
+                })
+            } else {
+                Err(ErrorCode::InternalError(format!("unknown conversion: {}", s)).into())
+            };
+        }
+        match s {
+            "bytes" | "string" | "asis" => Ok(Conversion::Identity),
+            "int" | "integer" => Ok(Conversion::Int),
+            "float" => Ok(Conversion::Float),
+            "bool" | "boolean" => Ok(Conversion::Bool),
+            "timestamp" => Ok(Conversion::Timestamp),
+            _ => Err(ErrorCode::InternalError(format!("unknown conversion: {}", s)).into()),
+        }
+    }
+}
+
+impl Conversion {
+    /// Parses a single non-null, non-empty value according to this conversion.
+    fn parse_one(&self, raw: &[u8]) -> Result<i64> {
+        let s = std::str::from_utf8(raw).map_err(|e| {
+            ErrorCode::InternalError(format!("invalid utf8 in {:?}: {}", raw, e))
+        })?;
+        match self {
+            // Handled before `parse_one` is ever called for a real value; see `cast()`.
+            Conversion::Identity => unreachable!("Conversion::cast short-circuits Identity"),
+            Conversion::Int => s
+                .parse::<i64>()
+                .map_err(|e| ErrorCode::InternalError(format!("invalid int {:?}: {}", s, e)).into()),
+            Conversion::Bool => match s {
+                "t" | "true" | "T" | "True" | "1" => Ok(1),
+                "f" | "false" | "F" | "False" | "0" => Ok(0),
+                _ => Err(ErrorCode::InternalError(format!("invalid bool {:?}", s)).into()),
+            },
+            Conversion::Timestamp => s
+                .parse::<i64>()
+                .map_err(|e| ErrorCode::InternalError(format!("invalid timestamp {:?}: {}", s, e)).into()),
+            Conversion::TimestampFormat { tz, format } => {
+                let naive = NaiveDateTime::parse_from_str(s, format).map_err(|e| {
+                    ErrorCode::InternalError(format!(
+                        "cannot parse {:?} as timestamp with format {:?}: {}",
+                        s, format, e
+                    ))
+                })?;
+                let utc = match tz {
+                    Some(tz) => {
+                        let tz: chrono_tz::Tz = tz.parse().map_err(|_| {
+                            ErrorCode::InternalError(format!("unknown timezone: {:?}", tz))
+                        })?;
+                        tz.from_local_datetime(&naive)
+                            .single()
+                            .ok_or_else(|| {
+                                ErrorCode::InternalError(format!(
+                                    "ambiguous local datetime {:?} in {:?}",
+                                    naive, tz
+                                ))
+                            })?
+                            .with_timezone(&Utc)
+                    }
+                    None => Utc.from_utc_datetime(&naive),
+                };
+                Ok(utc.timestamp())
+            }
+            Conversion::Float => {
+                Err(ErrorCode::InternalError("float conversion does not fit i64".to_string()).into())
+            }
+        }
+    }
+
+    /// Parses a single non-null, non-empty value as `f64`, for the [`Conversion::Float`] case.
+    fn parse_one_f64(&self, raw: &[u8]) -> Result<f64> {
+        let s = std::str::from_utf8(raw).map_err(|e| {
+            ErrorCode::InternalError(format!("invalid utf8 in {:?}: {}", raw, e))
+        })?;
+        s.parse::<f64>()
+            .map_err(|e| ErrorCode::InternalError(format!("invalid float {:?}: {}", s, e)).into())
+    }
+
+    /// Casts a bytes/UTF-8 array into a typed `ArrayImpl` per this conversion.
+    ///
+    /// A null or empty input stays null in the output bitmap; everything else is parsed via
+    /// [`Conversion::parse_one`]/[`Conversion::parse_one_f64`], so a malformed value surfaces as
+    /// an `Err` identifying the offending bytes. Length and the null bitmap are preserved exactly.
+    ///
+    /// `Bool` and `Timestamp`/`TimestampFormat` are encoded as `ArrayImpl::Int64` (0/1, and epoch
+    /// seconds, respectively) rather than as a dedicated boolean or timestamp array: `ArrayImpl`
+    /// in this checkout only defines the numeric variants built in this file
+    /// ([`PrimitiveArrayItemType`]'s impls above), with no `Bool`/timestamp variant to construct,
+    /// and `bool` has no [`crate::types::NativeType`] impl to back a `PrimitiveArray<bool>`
+    /// either. A caller that needs the real typed value can still recover it losslessly from the
+    /// `i64`; widening `ArrayImpl` itself is out of scope here.
+    ///
+    /// `Identity` (`"bytes"`/`"string"`/`"asis"`) has no numeric builder to go through at all, and
+    /// for the same reason as `Bool`/`Timestamp` above, `ArrayImpl` has no variable-length
+    /// string/bytes variant in this checkout to hand the raw bytes back in unchanged - so rather
+    /// than routing it through `parse_one` (which doesn't make sense for it and previously errored
+    /// on every real value with a misleading message), this returns a clear
+    /// `NotImplemented` up front.
+    pub fn cast<A>(&self, array: &A) -> Result<ArrayImpl>
+    where
+        A: Array,
+        for<'a> A::RefItem<'a>: AsRef<[u8]>,
+    {
+        if matches!(self, Conversion::Identity) {
+            return Err(ErrorCode::NotImplemented(
+                "Conversion::Identity passthrough (no string/bytes ArrayImpl variant exists in \
+                 this checkout to cast into)"
+                    .to_string(),
+                None.into(),
+            )
+            .into());
+        }
+
+        if matches!(self, Conversion::Float) {
+            let mut builder = PrimitiveArrayBuilder::<f64>::new(array.len())?;
+            for item in array.iter() {
+                match item {
+                    None => builder.append(None)?,
+                    Some(raw) if raw.as_ref().is_empty() => builder.append(None)?,
+                    Some(raw) => builder.append(Some(self.parse_one_f64(raw.as_ref())?))?,
+                }
+            }
+            return Ok(ArrayImpl::Float64(builder.finish()?));
+        }
+
+        let mut builder = PrimitiveArrayBuilder::<i64>::new(array.len())?;
+        for item in array.iter() {
+            match item {
+                None => builder.append(None)?,
+                Some(raw) if raw.as_ref().is_empty() => builder.append(None)?,
+                Some(raw) => builder.append(Some(self.parse_one(raw.as_ref())?))?,
+            }
+        }
+        Ok(ArrayImpl::Int64(builder.finish()?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,4 +555,126 @@ mod tests {
             unreachable!()
         }
     }
+
+    #[test]
+    fn test_u16_builder() {
+        let arr = helper_test_builder::<u16>(
+            (0..1000)
+                .map(|x| if x % 2 == 0 { None } else { Some(x) })
+                .collect(),
+        )
+        .unwrap();
+        if !matches!(ArrayImpl::from(arr), ArrayImpl::UInt16(_)) {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn test_u32_builder() {
+        let arr = helper_test_builder::<u32>(
+            (0..1000)
+                .map(|x| if x % 2 == 0 { None } else { Some(x) })
+                .collect(),
+        )
+        .unwrap();
+        if !matches!(ArrayImpl::from(arr), ArrayImpl::UInt32(_)) {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn test_u64_builder() {
+        let arr = helper_test_builder::<u64>(
+            (0..1000)
+                .map(|x| if x % 2 == 0 { None } else { Some(x as u64) })
+                .collect(),
+        )
+        .unwrap();
+        if !matches!(ArrayImpl::from(arr), ArrayImpl::UInt64(_)) {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn test_decimal_builder() {
+        let arr = helper_test_builder::<Decimal>(
+            (0..1000)
+                .map(|x| {
+                    if x % 2 == 0 {
+                        None
+                    } else {
+                        Some(Decimal::new(x as i128, 2))
+                    }
+                })
+                .collect(),
+        )
+        .unwrap();
+        assert_eq!(arr.value_at(1), Some(Decimal::new(1, 2)));
+        if !matches!(ArrayImpl::from(arr), ArrayImpl::Decimal(_)) {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn test_decimal_protobuf_round_trip() {
+        let arr = helper_test_builder::<Decimal>(vec![
+            Some(Decimal::new(12345, 2)),
+            None,
+            Some(Decimal::new(-987, 0)),
+        ])
+        .unwrap();
+        let bufs = arr.to_protobuf().unwrap();
+        let restored = PrimitiveArray::<Decimal>::from_protobuf(&bufs[0], 3, arr.bitmap.clone())
+            .unwrap();
+        assert_eq!(restored.value_at(0), arr.value_at(0));
+        assert_eq!(restored.value_at(1), arr.value_at(1));
+        assert_eq!(restored.value_at(2), arr.value_at(2));
+    }
+
+    #[test]
+    fn test_conversion_from_str() {
+        assert_eq!("bytes".parse::<Conversion>().unwrap(), Conversion::Identity);
+        assert_eq!("string".parse::<Conversion>().unwrap(), Conversion::Identity);
+        assert_eq!("asis".parse::<Conversion>().unwrap(), Conversion::Identity);
+        assert_eq!("int".parse::<Conversion>().unwrap(), Conversion::Int);
+        assert_eq!("integer".parse::<Conversion>().unwrap(), Conversion::Int);
+        assert_eq!("float".parse::<Conversion>().unwrap(), Conversion::Float);
+        assert_eq!("bool".parse::<Conversion>().unwrap(), Conversion::Bool);
+        assert_eq!("boolean".parse::<Conversion>().unwrap(), Conversion::Bool);
+        assert_eq!("timestamp".parse::<Conversion>().unwrap(), Conversion::Timestamp);
+        assert_eq!(
+            "timestamp|%Y-%m-%d".parse::<Conversion>().unwrap(),
+            Conversion::TimestampFormat {
+                tz: None,
+                format: "%Y-%m-%d".to_string()
+            }
+        );
+        assert_eq!(
+            "timestamp+Asia/Shanghai|%Y-%m-%d".parse::<Conversion>().unwrap(),
+            Conversion::TimestampFormat {
+                tz: Some("Asia/Shanghai".to_string()),
+                format: "%Y-%m-%d".to_string()
+            }
+        );
+        assert!("not_a_conversion".parse::<Conversion>().is_err());
+    }
+
+    #[test]
+    fn test_to_protobuf_from_protobuf_round_trip() {
+        let data: Vec<Option<i32>> = (0..2000)
+            .map(|x| if x % 7 == 0 { None } else { Some(x) })
+            .collect();
+        let arr = helper_test_builder::<i32>(data.clone()).unwrap();
+        let bufs = arr.to_protobuf().unwrap();
+        assert_eq!(bufs.len(), 1);
+        // A buffer this large should have been compressed rather than stored as NONE.
+        assert_eq!(bufs[0].get_compression(), Buffer_CompressionType::LZ4);
+
+        let restored =
+            PrimitiveArray::<i32>::from_protobuf(&bufs[0], data.len(), arr.bitmap.clone())
+                .unwrap();
+        for i in 0..data.len() {
+            assert_eq!(restored.value_at(i), arr.value_at(i));
+        }
+    }
 }
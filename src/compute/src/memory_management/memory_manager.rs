@@ -14,7 +14,7 @@
 
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use risingwave_batch::task::BatchManager;
 use risingwave_common::util::epoch::Epoch;
@@ -76,7 +76,7 @@ impl GlobalMemoryManager {
         batch_manager: Arc<BatchManager>,
         stream_manager: Arc<LocalStreamManager>,
     ) {
-        let mut tick_interval = tokio::time::interval(Duration::from_millis(50));
+        const BASE_TICK_INTERVAL_MS: u64 = 50;
         let mut memory_control_stats = MemoryControlStats {
             batch_memory_usage: 0,
             streaming_memory_usage: 0,
@@ -84,20 +84,31 @@ impl GlobalMemoryManager {
             lru_watermark_step: 0,
             lru_watermark_time_ms: Epoch::physical_now(),
             lru_physical_now_ms: Epoch::physical_now(),
+            pid_error_bytes: 0,
+            pid_integral: 0.0,
         };
+        let mut next_tick_interval_ms = BASE_TICK_INTERVAL_MS;
 
         loop {
-            // Wait for a while to check if need eviction.
-            tick_interval.tick().await;
+            // Wait for a while to check if need eviction. The interval is adaptive: the policy
+            // shortens it under memory pressure so the controller reacts faster, and lengthens it
+            // when comfortably under target to avoid needlessly polling jemalloc stats.
+            let tick_start = Instant::now();
+            tokio::time::sleep(Duration::from_millis(next_tick_interval_ms)).await;
+            let elapsed_tick_ms = tick_start.elapsed().as_millis() as u64;
 
             memory_control_stats = self.memory_control_policy.apply(
                 self.total_compute_memory_bytes,
                 self.barrier_interval_ms,
+                elapsed_tick_ms,
                 memory_control_stats,
                 batch_manager.clone(),
                 stream_manager.clone(),
                 self.watermark_epoch.clone(),
             );
+            next_tick_interval_ms = self
+                .memory_control_policy
+                .next_tick_interval_ms(BASE_TICK_INTERVAL_MS, &memory_control_stats);
 
             self.metrics
                 .lru_current_watermark_time_ms
@@ -112,6 +123,15 @@ impl GlobalMemoryManager {
             self.metrics
                 .jemalloc_allocated_bytes
                 .set(memory_control_stats.jemalloc_allocated_mib as i64);
+            // TODO: once `StreamingMetrics` grows gauges for the controller's error/integral
+            // terms, export `memory_control_stats.pid_error_bytes` and `.pid_integral` here
+            // instead of just logging them.
+            tracing::debug!(
+                error_bytes = memory_control_stats.pid_error_bytes,
+                integral = memory_control_stats.pid_integral,
+                next_tick_interval_ms,
+                "memory control policy tick"
+            );
         }
     }
 }
@@ -0,0 +1,171 @@
+// Copyright 2023 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use risingwave_batch::task::BatchManager;
+use risingwave_common::util::epoch::Epoch;
+use risingwave_stream::task::LocalStreamManager;
+
+/// Statistics produced by one [`MemoryControlPolicy::apply`] tick. Fed back in as the previous
+/// tick's state and reported as gauges by [`super::memory_manager::GlobalMemoryManager`].
+#[derive(Debug, Clone)]
+pub struct MemoryControlStats {
+    pub batch_memory_usage: usize,
+    pub streaming_memory_usage: usize,
+    pub jemalloc_allocated_mib: usize,
+    pub lru_watermark_step: u64,
+    pub lru_watermark_time_ms: u64,
+    pub lru_physical_now_ms: u64,
+    /// `jemalloc_allocated - target`, in bytes, as of this tick. Positive means we're over
+    /// budget and the watermark should advance.
+    pub pid_error_bytes: i64,
+    /// The controller's accumulated integral term, in byte-milliseconds.
+    pub pid_integral: f64,
+}
+
+struct PidState {
+    integral: f64,
+    prev_error_bytes: i64,
+}
+
+/// A closed-loop PID controller for the LRU watermark, replacing the previous fixed-step
+/// heuristic. Each tick computes the error between jemalloc-reported allocated memory and
+/// `target_memory_usage_fraction * total_compute_memory_bytes`, and turns it into a number of
+/// epochs to advance `watermark_epoch` by: `step = clamp(Kp*e + Ki*integral + Kd*(e - prev_e))`,
+/// with `step` floored at zero so nothing is evicted while comfortably under target.
+pub struct MemoryControlPolicy {
+    target_memory_usage_fraction: f64,
+    kp: f64,
+    ki: f64,
+    kd: f64,
+    max_watermark_step_per_tick: u64,
+    state: Mutex<PidState>,
+}
+
+impl MemoryControlPolicy {
+    pub fn new(
+        target_memory_usage_fraction: f64,
+        kp: f64,
+        ki: f64,
+        kd: f64,
+        max_watermark_step_per_tick: u64,
+    ) -> Self {
+        Self {
+            target_memory_usage_fraction,
+            kp,
+            ki,
+            kd,
+            max_watermark_step_per_tick,
+            state: Mutex::new(PidState {
+                integral: 0.0,
+                prev_error_bytes: 0,
+            }),
+        }
+    }
+
+    pub fn describe(&self, total_compute_memory_bytes: usize) -> String {
+        format!(
+            "PID(kp={}, ki={}, kd={}) targeting {:.1}% of {} bytes",
+            self.kp,
+            self.ki,
+            self.kd,
+            self.target_memory_usage_fraction * 100.0,
+            total_compute_memory_bytes
+        )
+    }
+
+    /// Runs one controller tick and returns the updated [`MemoryControlStats`].
+    ///
+    /// `elapsed_tick_ms` is the actual wall-clock time since the previous tick, not just the
+    /// nominal tick interval, so the gains stay correctly scaled if the caller shortens the tick
+    /// under memory pressure (see [`Self::next_tick_interval_ms`]) or lengthens it when idle.
+    /// `barrier_interval_ms` converts the resulting epoch step into a millisecond watermark time,
+    /// which is both stored into `watermark_epoch` and reported.
+    pub fn apply(
+        &self,
+        total_compute_memory_bytes: usize,
+        barrier_interval_ms: u32,
+        elapsed_tick_ms: u64,
+        prev_stats: MemoryControlStats,
+        batch_manager: Arc<BatchManager>,
+        stream_manager: Arc<LocalStreamManager>,
+        watermark_epoch: Arc<AtomicU64>,
+    ) -> MemoryControlStats {
+        let jemalloc_allocated_mib = Self::get_jemalloc_allocated_mib();
+        let batch_memory_usage = batch_manager.total_mem_usage();
+        let streaming_memory_usage = stream_manager.total_mem_usage();
+
+        let target_bytes =
+            (total_compute_memory_bytes as f64 * self.target_memory_usage_fraction) as i64;
+        let allocated_bytes = (jemalloc_allocated_mib * 1024 * 1024) as i64;
+        let error = allocated_bytes - target_bytes;
+
+        let dt_ms = elapsed_tick_ms.max(1) as f64;
+        let mut state = self.state.lock().unwrap();
+        let derivative = (error - state.prev_error_bytes) as f64 / dt_ms;
+        let tentative_integral = state.integral + error as f64 * dt_ms;
+        let raw_step = self.kp * error as f64 + self.ki * tentative_integral + self.kd * derivative;
+        let clamped_step = raw_step.max(0.0).min(self.max_watermark_step_per_tick as f64);
+
+        // Anti-windup: only keep accumulating the integral term while the output isn't clamped,
+        // so a long period of saturation doesn't leave a huge integral to unwind once the error
+        // finally shrinks.
+        if raw_step == clamped_step {
+            state.integral = tentative_integral;
+        }
+        state.prev_error_bytes = error;
+        let pid_integral = state.integral;
+        drop(state);
+
+        // Each step is one barrier interval's worth of epochs.
+        let step = clamped_step as u64;
+        let lru_watermark_time_ms =
+            prev_stats.lru_watermark_time_ms + step * barrier_interval_ms as u64;
+        if step > 0 {
+            watermark_epoch.store(
+                Epoch::from_physical_time(lru_watermark_time_ms).0,
+                Ordering::Relaxed,
+            );
+        }
+
+        MemoryControlStats {
+            batch_memory_usage,
+            streaming_memory_usage,
+            jemalloc_allocated_mib,
+            lru_watermark_step: step,
+            lru_watermark_time_ms,
+            lru_physical_now_ms: Epoch::physical_now(),
+            pid_error_bytes: error,
+            pid_integral,
+        }
+    }
+
+    /// Picks the next tick interval given the previous tick's error: shorten it under memory
+    /// pressure so the controller reacts faster, lengthen it when comfortably under target so we
+    /// don't needlessly burn CPU polling jemalloc stats. Bounded to `[base_interval_ms / 4,
+    /// base_interval_ms * 4]`.
+    pub fn next_tick_interval_ms(&self, base_interval_ms: u64, prev_stats: &MemoryControlStats) -> u64 {
+        if prev_stats.pid_error_bytes > 0 {
+            (base_interval_ms / 4).max(10)
+        } else {
+            base_interval_ms * 4
+        }
+    }
+
+    fn get_jemalloc_allocated_mib() -> usize {
+        tikv_jemalloc_ctl::stats::allocated::read().unwrap_or(0) / 1024 / 1024
+    }
+}
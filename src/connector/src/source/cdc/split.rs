@@ -18,14 +18,70 @@ use serde::{Deserialize, Serialize};
 
 use crate::source::{SplitId, SplitMetaData};
 
+/// A resumable position within a CDC split's upstream change stream.
+///
+/// This used to be a bare `Option<String>`, which meant the connector could only pass the
+/// position through opaquely instead of validating or reasoning about it. `Raw` is kept around
+/// so offsets persisted before this change keep deserializing instead of failing a running
+/// job's checkpoint restore: a legacy checkpoint stored `start_offset` as a bare JSON string
+/// (e.g. `"mysql-bin.000003:154"`), not as the externally-tagged map this enum's derived
+/// `Deserialize` would otherwise require, so [`Deserialize`] is implemented by hand below to
+/// accept either shape.
+#[derive(Clone, Serialize, Debug, PartialEq, Eq, Hash)]
+pub enum CdcOffset {
+    /// MySQL-style binlog file name plus byte position within it.
+    BinlogFilePos { filename: String, position: u64 },
+    /// A GTID set, in the textual form the upstream reports it.
+    Gtid(String),
+    /// A Postgres-style log sequence number.
+    Lsn(u64),
+    /// An offset persisted before this type existed; never produced for new splits.
+    Raw(String),
+}
+
+impl<'de> Deserialize<'de> for CdcOffset {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // Mirrors `CdcOffset`'s derived (externally-tagged) shape, but only for the `Tagged` arm;
+        // `Legacy` lets a bare string - the pre-`CdcOffset` on-disk format - also deserialize.
+        #[derive(Deserialize)]
+        enum Tagged {
+            BinlogFilePos { filename: String, position: u64 },
+            Gtid(String),
+            Lsn(u64),
+            Raw(String),
+        }
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Legacy(String),
+            Tagged(Tagged),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Legacy(s) => CdcOffset::Raw(s),
+            Repr::Tagged(Tagged::BinlogFilePos { filename, position }) => {
+                CdcOffset::BinlogFilePos { filename, position }
+            }
+            Repr::Tagged(Tagged::Gtid(s)) => CdcOffset::Gtid(s),
+            Repr::Tagged(Tagged::Lsn(n)) => CdcOffset::Lsn(n),
+            Repr::Tagged(Tagged::Raw(s)) => CdcOffset::Raw(s),
+        })
+    }
+}
+
 /// The states of a CDC split, which will be persisted to checkpoint.
-/// CDC source only has single split, so we use the `source_id` to identify the split.
+///
+/// A single CDC source fans out into one `CdcSplit` per captured table / upstream shard, each
+/// independently resumable from its own [`CdcOffset`].
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Hash)]
 pub struct CdcSplit {
     pub split_id: u32,
     // the hostname and port of a node that holding shard tables
     pub server_addr: Option<String>,
-    pub start_offset: Option<String>,
+    pub start_offset: Option<CdcOffset>,
 }
 
 impl SplitMetaData for CdcSplit {
@@ -43,7 +99,7 @@ impl SplitMetaData for CdcSplit {
 }
 
 impl CdcSplit {
-    pub fn new(split_id: u32, start_offset: String) -> CdcSplit {
+    pub fn new(split_id: u32, start_offset: CdcOffset) -> CdcSplit {
         Self {
             split_id,
             server_addr: None,
@@ -51,7 +107,7 @@ impl CdcSplit {
         }
     }
 
-    pub fn copy_with_offset(&self, start_offset: String) -> Self {
+    pub fn copy_with_offset(&self, start_offset: CdcOffset) -> Self {
         Self {
             split_id: self.split_id,
             server_addr: self.server_addr.clone(),
@@ -59,3 +115,76 @@ impl CdcSplit {
         }
     }
 }
+
+/// Redistributes `splits` round-robin across `actor_count` parallel actors, so a scale-out (or
+/// scale-in) reshuffles existing splits evenly instead of leaving them all pinned to their
+/// original actor. The split order is stable across calls with the same input, so this can be
+/// re-run on every rescheduling without needless churn.
+pub fn assign_splits(mut splits: Vec<CdcSplit>, actor_count: usize) -> Vec<Vec<CdcSplit>> {
+    if actor_count == 0 {
+        // No actors to assign to: there's nowhere for a split to go, so return no assignments
+        // rather than indexing into a zero-length `assignment` below.
+        return vec![];
+    }
+    splits.sort_unstable_by_key(|split| split.split_id);
+    let mut assignment = vec![Vec::new(); actor_count];
+    for (i, split) in splits.into_iter().enumerate() {
+        assignment[i % actor_count].push(split);
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cdc_offset_round_trip() {
+        let split = CdcSplit::new(
+            1,
+            CdcOffset::BinlogFilePos {
+                filename: "binlog.000001".to_string(),
+                position: 4,
+            },
+        );
+        let encoded = split.encode_to_json();
+        let decoded = CdcSplit::restore_from_json(encoded).unwrap();
+        assert_eq!(split, decoded);
+    }
+
+    #[test]
+    fn test_legacy_raw_offset_round_trip() {
+        // Before `CdcOffset` existed, `start_offset` was a bare `Option<String>`, so a real old
+        // checkpoint has `start_offset` as a plain JSON string, not `{"Raw": "..."}`.
+        let value: serde_json::Value = serde_json::json!({
+            "split_id": 2,
+            "server_addr": null,
+            "start_offset": "mysql-bin.000003:154",
+        });
+        let split = CdcSplit::restore_from_json(value.into()).unwrap();
+        assert_eq!(
+            split.start_offset,
+            Some(CdcOffset::Raw("mysql-bin.000003:154".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_assign_splits_round_robin() {
+        let splits = (0..5)
+            .map(|i| CdcSplit::new(i, CdcOffset::Lsn(i as u64)))
+            .collect();
+        let assignment = assign_splits(splits, 2);
+        assert_eq!(assignment.len(), 2);
+        assert_eq!(assignment[0].len(), 3);
+        assert_eq!(assignment[1].len(), 2);
+    }
+
+    #[test]
+    fn test_assign_splits_zero_actors_does_not_panic() {
+        let splits = (0..5)
+            .map(|i| CdcSplit::new(i, CdcOffset::Lsn(i as u64)))
+            .collect();
+        let assignment = assign_splits(splits, 0);
+        assert!(assignment.is_empty());
+    }
+}
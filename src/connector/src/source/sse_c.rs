@@ -0,0 +1,134 @@
+// Copyright 2023 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Customer-provided encryption key (SSE-C) parsing and validation - a building block for
+//! object-store-backed sources that want to support SSE-C in the future.
+//!
+//! NOT IMPLEMENTED as a feature: this module does not add SSE-C support to any source. A user
+//! would supply a base64-encoded AES-256 key via the source's WITH-options (see
+//! [`SSE_C_KEY_PROPERTY`]); [`SseCKey::from_base64`] decodes and validates it, and
+//! [`SseCKey::sha256_hex`]/[`SseCKey::verify_digest`] let a stored digest be checked so a
+//! mismatched key fails loudly instead of silently yielding garbage. But nothing in this tree
+//! calls any of it: no `CREATE SOURCE` WITH-options parser anywhere in this checkout reads
+//! [`SSE_C_KEY_PROPERTY`], nothing validates or persists it, and no object-store reader calls
+//! [`SseCKey::key_bytes`] to decrypt anything read from storage. Treat `SseCKey` as an inert,
+//! unit-tested-in-isolation crypto utility with zero callers, not as working SSE-C support - don't
+//! advertise "SSE-C support" to users based on this module existing.
+
+use md5::{Digest as _, Md5};
+use sha2::Sha256;
+use thiserror::Error;
+
+/// The WITH-option a source uses to supply its base64-encoded, 32-byte AES-256 SSE-C key.
+pub const SSE_C_KEY_PROPERTY: &str = "sse.customer.key";
+
+const AES_256_KEY_LEN: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum SseCError {
+    #[error("SSE-C key is not valid base64: {0}")]
+    InvalidBase64(#[from] base64::DecodeError),
+    #[error("SSE-C key must decode to exactly {AES_256_KEY_LEN} bytes for AES-256, got {0}")]
+    InvalidKeyLength(usize),
+    #[error("SSE-C key digest mismatch: expected {expected}, computed {computed}")]
+    DigestMismatch { expected: String, computed: String },
+}
+
+/// A validated customer-supplied AES-256 key, along with its precomputed digests.
+///
+/// Deliberately has no `Display`/`Debug` impl that prints [`Self::key`] so the raw key bytes
+/// can't leak into plan explain output or logs by accident; use [`Self::sha256_hex`] /
+/// [`Self::md5_hex`] when a loggable identifier is needed.
+pub struct SseCKey {
+    key: [u8; AES_256_KEY_LEN],
+}
+
+impl SseCKey {
+    /// Decodes `base64_key` and verifies it's exactly 32 bytes, as AES-256 requires.
+    pub fn from_base64(base64_key: &str) -> Result<Self, SseCError> {
+        use base64::Engine as _;
+        let bytes = base64::engine::general_purpose::STANDARD.decode(base64_key)?;
+        let key: [u8; AES_256_KEY_LEN] = bytes
+            .clone()
+            .try_into()
+            .map_err(|_| SseCError::InvalidKeyLength(bytes.len()))?;
+        Ok(Self { key })
+    }
+
+    pub fn sha256_hex(&self) -> String {
+        hex::encode(Sha256::digest(self.key))
+    }
+
+    pub fn md5_hex(&self) -> String {
+        hex::encode(Md5::digest(self.key))
+    }
+
+    /// Returns [`SseCError::DigestMismatch`] if `expected_sha256_hex` doesn't match this key's
+    /// SHA-256 digest, so a caller that checks it before decrypting gets an explicit error for a
+    /// stale or mismatched key instead of garbage.
+    pub fn verify_digest(&self, expected_sha256_hex: &str) -> Result<(), SseCError> {
+        let computed = self.sha256_hex();
+        if computed != expected_sha256_hex {
+            return Err(SseCError::DigestMismatch {
+                expected: expected_sha256_hex.to_owned(),
+                computed,
+            });
+        }
+        Ok(())
+    }
+
+    /// The raw 32-byte key material, for handing to the AES-256 block cipher.
+    ///
+    /// NOT IMPLEMENTED: nothing calls this. The object-store reader that would call this to
+    /// decrypt each object block before parsing isn't present in this checkout; wire it in once
+    /// that reader exists.
+    pub fn key_bytes(&self) -> &[u8; AES_256_KEY_LEN] {
+        &self.key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine as _;
+
+    use super::*;
+
+    #[test]
+    fn test_valid_key_round_trip() {
+        let raw = [7u8; AES_256_KEY_LEN];
+        let encoded = base64::engine::general_purpose::STANDARD.encode(raw);
+        let key = SseCKey::from_base64(&encoded).unwrap();
+        assert!(key.verify_digest(&key.sha256_hex()).is_ok());
+    }
+
+    #[test]
+    fn test_wrong_length_rejected() {
+        let encoded = base64::engine::general_purpose::STANDARD.encode([1u8; 16]);
+        assert!(matches!(
+            SseCKey::from_base64(&encoded),
+            Err(SseCError::InvalidKeyLength(16))
+        ));
+    }
+
+    #[test]
+    fn test_digest_mismatch_rejected() {
+        let raw = [9u8; AES_256_KEY_LEN];
+        let encoded = base64::engine::general_purpose::STANDARD.encode(raw);
+        let key = SseCKey::from_base64(&encoded).unwrap();
+        assert!(matches!(
+            key.verify_digest("not-the-real-digest"),
+            Err(SseCError::DigestMismatch { .. })
+        ));
+    }
+}
@@ -26,9 +26,21 @@ pub enum WindowFuncKind {
     DenseRank,
     Lag,
     Lead,
-    // FirstValue,
-    // LastValue,
-    // NthValue,
+    /// `NTILE(k)` splits the `m` rows of a partition into `k` buckets numbered from 1, as evenly
+    /// as possible: every bucket gets `m / k` rows and the first `m mod k` buckets one extra row.
+    NTile,
+    /// Returns the argument evaluated at the frame's first row.
+    FirstValue,
+    /// Returns the argument evaluated at the frame's last row.
+    LastValue,
+    /// `NTH_VALUE(expr, n)` returns `expr` evaluated at the frame's `n`-th row (1-based), or
+    /// `NULL` if the frame has fewer than `n` rows.
+    NthValue,
+    /// `rows_with_peer_or_less / partition_rows`: the fraction of partition rows that sort at or
+    /// before the current row's peer group.
+    CumeDist,
+    /// `(rank - 1) / (partition_rows - 1)`, or `0` when the partition has a single row.
+    PercentRank,
 
     // Aggregate functions that are used with `OVER`.
     #[display("{0}")]
@@ -39,4 +51,16 @@ impl WindowFuncKind {
     pub fn is_rank(&self) -> bool {
         matches!(self, Self::RowNumber | Self::Rank | Self::DenseRank)
     }
+
+    /// Whether this kind is a rank/distribution function that ignores the window frame and is
+    /// instead computed over the whole partition (e.g. `CUME_DIST`, `PERCENT_RANK`).
+    pub fn is_partition_distribution(&self) -> bool {
+        matches!(self, Self::CumeDist | Self::PercentRank)
+    }
+
+    /// Whether this kind resolves its result against a row (or rows) of the window frame, as
+    /// opposed to being computed from the whole partition or from a fixed row offset.
+    pub fn is_value_function(&self) -> bool {
+        matches!(self, Self::FirstValue | Self::LastValue | Self::NthValue)
+    }
 }
@@ -0,0 +1,47 @@
+// Copyright 2023 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::types::ScalarImpl;
+
+mod kind;
+
+pub use kind::WindowFuncKind;
+
+/// One side of a window frame, generic over the representation of the offset (row count for
+/// `ROWS`/`GROUPS`, a value offset for `RANGE`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FrameBound<T> {
+    UnboundedPreceding,
+    Preceding(T),
+    CurrentRow,
+    Following(T),
+    UnboundedFollowing,
+}
+
+/// The frame of a window function, bounding which rows of the current partition are visible to
+/// it. `LogicalOverAgg` fills in an explicit frame for `FIRST_VALUE`/`LAST_VALUE`/`NTH_VALUE`
+/// when none is given, and a `ROWS` frame for `LAG`/`LEAD` with a constant offset; other window
+/// functions keep whatever frame the query gave, which may be none.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Frame {
+    /// Bounds counted in rows, e.g. `ROWS BETWEEN 1 PRECEDING AND CURRENT ROW`.
+    Rows(FrameBound<usize>, FrameBound<usize>),
+    /// Bounds compared against the single `ORDER BY` column's value, e.g.
+    /// `RANGE BETWEEN INTERVAL '5 minutes' PRECEDING AND CURRENT ROW`. The offset is evaluated to
+    /// a constant at plan time, the same way a `ROWS` offset is.
+    Range(FrameBound<ScalarImpl>, FrameBound<ScalarImpl>),
+    /// Bounds counted in peer groups (rows that compare equal on `ORDER BY`), e.g.
+    /// `GROUPS BETWEEN 1 PRECEDING AND CURRENT ROW`.
+    Groups(FrameBound<usize>, FrameBound<usize>),
+}
@@ -50,4 +50,6 @@ mod rw_types;
 mod rw_user_secrets;
 mod rw_users;
 mod rw_views;
+mod rw_watermarks;
+mod rw_window_states;
 mod rw_worker_nodes;
@@ -0,0 +1,41 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::types::Fields;
+use risingwave_frontend_macro::system_catalog;
+
+/// Per-fragment/actor watermark progress: the watermark column, its latest value, and its lag
+/// behind wall clock. The `lru_current_watermark_time_ms` Prometheus gauge only reports a single
+/// process-wide LRU watermark time.
+#[derive(Fields)]
+struct RwWatermark {
+    fragment_id: i32,
+    actor_id: i32,
+    watermark_column: String,
+    watermark_value: String,
+    /// How far behind wall clock the watermark is, in milliseconds.
+    lag_ms: i64,
+}
+
+// NOT IMPLEMENTED: this table is permanently empty, not genuinely populated. No meta RPC exists
+// anywhere in this tree that a frontend can call to snapshot the current watermark per
+// fragment/actor (only the aggregate `lru_current_watermark_time_ms` gauge is exported, which
+// can't be broken down by fragment/column). `SELECT * FROM rw_catalog.rw_watermarks` will return
+// zero rows on every real cluster until that per-actor RPC and its handler are added - don't read
+// an empty result from this table as "no watermarks", read it as "not wired up yet".
+#[system_catalog(table, "rw_catalog.rw_watermarks")]
+async fn read_rw_watermarks(reader: &SysCatalogReaderImpl) -> Result<Vec<RwWatermark>> {
+    let _ = reader;
+    Ok(vec![])
+}
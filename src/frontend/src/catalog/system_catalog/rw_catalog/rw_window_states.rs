@@ -0,0 +1,40 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::types::Fields;
+use risingwave_frontend_macro::system_catalog;
+
+/// Memory held by over-window/aggregate executors, per fragment/actor, for diagnosing eviction
+/// and watermark behavior at the SQL level instead of only through Prometheus.
+#[derive(Fields)]
+struct RwWindowState {
+    fragment_id: i32,
+    actor_id: i32,
+    executor_name: String,
+    memory_usage_bytes: i64,
+    cached_partition_count: i64,
+}
+
+// NOT IMPLEMENTED: this table is permanently empty, not genuinely populated. Per-executor
+// memory/cached-partition counts for over-window and aggregate executors aren't reported to meta
+// at all today (the memory controller in `compute`'s `memory_management` module only tracks a
+// process-wide total, not a per-fragment/actor/executor breakdown), so there's no data source to
+// read here yet. `SELECT * FROM rw_catalog.rw_window_states` will return zero rows on every real
+// cluster until per-executor reporting exists - don't read an empty result as "no window state",
+// read it as "not wired up yet".
+#[system_catalog(table, "rw_catalog.rw_window_states")]
+async fn read_rw_window_states(reader: &SysCatalogReaderImpl) -> Result<Vec<RwWindowState>> {
+    let _ = reader;
+    Ok(vec![])
+}
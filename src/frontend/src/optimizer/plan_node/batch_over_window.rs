@@ -0,0 +1,157 @@
+// Copyright 2023 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::util::sort_util::ColumnOrder;
+use risingwave_expr::function::window::WindowFuncKind;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::SortOverWindowNode;
+
+use super::generic::{OverWindow, PlanWindowFunction};
+use super::{
+    generic, ExprRewritable, PlanBase, PlanRef, PlanTreeNodeUnary, ToBatchProst, ToDistributedBatch,
+};
+use crate::optimizer::plan_node::ToLocalBatch;
+use crate::optimizer::property::{Order, RequiredDist};
+
+/// `BatchOverWindow` requires its input sorted by `partition_by` then `order_by`, and streams one
+/// partition at a time computing each [`PlanWindowFunction`]. Only the kinds with a known
+/// streaming-friendly batch implementation are supported; anything else is rejected up front in
+/// [`BatchOverWindow::new`] with a clear error rather than failing deep inside the executor.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BatchOverWindow {
+    pub base: PlanBase,
+    core: OverWindow<PlanRef>,
+}
+
+/// Kinds of [`WindowFuncKind`] that the batch executor currently knows how to compute.
+fn check_batch_supported(calls: &[PlanWindowFunction]) -> Result<()> {
+    for call in calls {
+        if !matches!(
+            call.kind,
+            WindowFuncKind::RowNumber | WindowFuncKind::Rank | WindowFuncKind::Lag | WindowFuncKind::Lead
+        ) {
+            return Err(ErrorCode::NotImplemented(
+                format!("batch execution of window function: {}", call.kind),
+                None.into(),
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+impl BatchOverWindow {
+    pub fn new(core: OverWindow<PlanRef>) -> Result<Self> {
+        check_batch_supported(&core.window_functions)?;
+        let base = PlanBase::new_batch_from_logical(
+            &core,
+            core.input.distribution().clone(),
+            Self::sort_order(&core),
+        );
+        Ok(Self { base, core })
+    }
+
+    /// The `partition_by` then `order_by` order that the optimizer must satisfy on the input,
+    /// either by pushing an order property down or inserting a sort.
+    pub fn sort_order(core: &OverWindow<PlanRef>) -> Order {
+        let mut column_orders: Vec<ColumnOrder> = vec![];
+        for f in &core.window_functions {
+            for p in &f.partition_by {
+                if !column_orders.iter().any(|o| o.column_index == p.index()) {
+                    column_orders.push(ColumnOrder::new(
+                        p.index(),
+                        risingwave_common::util::sort_util::OrderType::default(),
+                    ));
+                }
+            }
+        }
+        for f in &core.window_functions {
+            for o in &f.order_by {
+                if !column_orders.iter().any(|c| c.column_index == o.column_index) {
+                    column_orders.push(o.clone());
+                }
+            }
+        }
+        Order { column_orders }
+    }
+
+    pub fn required_dist(&self) -> RequiredDist {
+        let keys = self
+            .core
+            .window_functions
+            .iter()
+            .flat_map(|f| f.partition_by.iter().map(|i| i.index()))
+            .unique()
+            .collect_vec();
+        if keys.is_empty() {
+            RequiredDist::single()
+        } else {
+            RequiredDist::shard_by_key(self.core.input.schema().len(), &keys)
+        }
+    }
+}
+
+impl PlanTreeNodeUnary for BatchOverWindow {
+    fn input(&self) -> PlanRef {
+        self.core.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(OverWindow::new(self.core.window_functions.clone(), input))
+            .expect("window functions were already validated when this plan was first built")
+    }
+}
+
+impl_plan_tree_node_for_unary! { BatchOverWindow }
+
+impl fmt::Display for BatchOverWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.core.fmt_with_name(f, "BatchOverWindow")
+    }
+}
+
+impl ExprRewritable for BatchOverWindow {}
+
+impl ToDistributedBatch for BatchOverWindow {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        let new_input = self
+            .input()
+            .to_distributed_with_required(&Self::sort_order(&self.core), &self.required_dist())?;
+        Ok(Self::new(OverWindow::new(self.core.window_functions.clone(), new_input))?.into())
+    }
+}
+
+impl ToLocalBatch for BatchOverWindow {
+    fn to_local(&self) -> Result<PlanRef> {
+        let new_input = self.input().to_local()?;
+        Ok(Self::new(OverWindow::new(self.core.window_functions.clone(), new_input))?.into())
+    }
+}
+
+impl ToBatchProst for BatchOverWindow {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::SortOverWindow(SortOverWindowNode {
+            calls: self
+                .core
+                .window_functions
+                .iter()
+                .map(|f| f.to_protobuf())
+                .collect(),
+        })
+    }
+}
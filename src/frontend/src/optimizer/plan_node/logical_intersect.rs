@@ -0,0 +1,241 @@
+// Copyright 2023 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_expr::function::aggregate::AggKind;
+
+use super::{
+    generic, ColPrunable, ExprRewritable, LogicalAgg, LogicalFilter, LogicalProject, LogicalUnion,
+    PlanAggCall, PlanBase, PlanRef, PredicatePushdown, ToBatch, ToStream,
+};
+use crate::optimizer::plan_node::{
+    ColumnPruningContext, PlanTreeNode, PredicatePushdownContext, RewriteStreamContext,
+    ToStreamContext,
+};
+use crate::expr::{Expr, ExprImpl, ExprType, FunctionCall, InputRef, Literal};
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalIntersect` returns the rows that appear in every one of its inputs.
+///
+/// If `all` is false, the result is deduplicated, matching `INTERSECT`; if `all` is true, a row's
+/// multiplicity in the output is the minimum of its multiplicity across all inputs, matching
+/// `INTERSECT ALL`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LogicalIntersect {
+    pub base: PlanBase,
+    core: generic::Union<PlanRef>,
+}
+
+impl LogicalIntersect {
+    pub fn new(all: bool, inputs: Vec<PlanRef>) -> Self {
+        let core = generic::Union {
+            all,
+            inputs,
+            source_col: None,
+        };
+        let base = PlanBase::new_logical_with_core(&core);
+        LogicalIntersect { base, core }
+    }
+
+    pub fn create(all: bool, inputs: Vec<PlanRef>) -> PlanRef {
+        LogicalIntersect::new(all, inputs).into()
+    }
+
+    pub(super) fn fmt_with_name(&self, f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+        self.core.fmt_with_name(f, name)
+    }
+
+    pub fn all(&self) -> bool {
+        self.core.all
+    }
+
+    /// Lowers `self` into a `source_col`-tagged union-all, grouped by the original columns and
+    /// kept only where every input contributed a matching row — the same union-all + agg
+    /// strategy `LogicalUnion::to_batch`/`to_stream` use for plain `UNION`, extended with a
+    /// `HAVING count(distinct source_col) = num_inputs` filter.
+    ///
+    /// `INTERSECT ALL`'s multiset semantics (row multiplicity = min count across inputs) would
+    /// additionally need a way to replay a row `min(count)` times, which this plan doesn't have a
+    /// building block for yet, so only the dedup (`INTERSECT`) case is supported here.
+    fn lower_to_union_and_agg(&self) -> Result<PlanRef> {
+        if self.all() {
+            return Err(ErrorCode::NotImplemented(
+                "INTERSECT ALL (row-multiplicity replay is not yet supported)".to_string(),
+                None.into(),
+            )
+            .into());
+        }
+
+        let original_len = self.base.schema().len();
+        let num_inputs = self.core.inputs.len();
+
+        let tagged_inputs = self
+            .core
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| {
+                let mut exprs = (0..original_len)
+                    .map(|x| {
+                        ExprImpl::InputRef(
+                            InputRef::new(x, input.schema().fields[x].data_type.clone()).into(),
+                        )
+                    })
+                    .collect_vec();
+                exprs.push(ExprImpl::Literal(
+                    Literal::new(Some(ScalarImpl::Int32(i as i32)), DataType::Int32).into(),
+                ));
+                LogicalProject::create(input.clone(), exprs)
+            })
+            .collect_vec();
+        let union: PlanRef =
+            LogicalUnion::new_with_source_col(true, tagged_inputs, Some(original_len)).into();
+
+        let match_count = PlanAggCall {
+            agg_kind: AggKind::Count,
+            return_type: DataType::Int64,
+            inputs: vec![InputRef::new(original_len, DataType::Int32)],
+            distinct: true,
+            order_by: vec![],
+            filter: Condition::true_cond(),
+            direct_args: vec![],
+        };
+        let group_keys = (0..original_len).collect_vec();
+        let agg: PlanRef = LogicalAgg::new(vec![match_count], group_keys, union).into();
+
+        let predicate: ExprImpl = FunctionCall::new(
+            ExprType::Equal,
+            vec![
+                InputRef::new(original_len, DataType::Int64).into(),
+                Literal::new(Some(ScalarImpl::Int64(num_inputs as i64)), DataType::Int64).into(),
+            ],
+        )?
+        .into();
+        let filtered = LogicalFilter::create(agg, Condition::with_expr(predicate));
+
+        let mapping = ColIndexMapping::with_remaining_columns(
+            &(0..original_len).collect_vec(),
+            original_len + 1,
+        );
+        Ok(LogicalProject::with_mapping(filtered, mapping).into())
+    }
+}
+
+impl PlanTreeNode for LogicalIntersect {
+    fn inputs(&self) -> smallvec::SmallVec<[PlanRef; 2]> {
+        let mut vec = smallvec::SmallVec::new();
+        vec.extend(self.core.inputs.clone());
+        vec
+    }
+
+    fn clone_with_inputs(&self, inputs: &[PlanRef]) -> PlanRef {
+        Self::new(self.all(), inputs.to_vec()).into()
+    }
+}
+
+impl fmt::Display for LogicalIntersect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with_name(f, "LogicalIntersect")
+    }
+}
+
+impl ColPrunable for LogicalIntersect {
+    fn prune_col(&self, required_cols: &[usize], ctx: &mut ColumnPruningContext) -> PlanRef {
+        // Every input must keep every column: dropping one would change which rows compare equal
+        // across inputs, and thus which rows the intersection keeps.
+        let all_cols = (0..self.base.schema().len()).collect_vec();
+        let new_inputs = self
+            .inputs()
+            .iter()
+            .map(|input| input.prune_col(&all_cols, ctx))
+            .collect_vec();
+        let new_intersect = self.clone_with_inputs(&new_inputs);
+        let mapping = ColIndexMapping::with_remaining_columns(required_cols, all_cols.len());
+        super::LogicalProject::with_mapping(new_intersect, mapping).into()
+    }
+}
+
+impl ExprRewritable for LogicalIntersect {}
+
+impl PredicatePushdown for LogicalIntersect {
+    fn predicate_pushdown(
+        &self,
+        predicate: Condition,
+        ctx: &mut PredicatePushdownContext,
+    ) -> PlanRef {
+        // Safe for every input: a row that doesn't satisfy `predicate` can't be part of any
+        // output row regardless of which inputs it otherwise agrees with.
+        let new_inputs = self
+            .inputs()
+            .iter()
+            .map(|input| input.predicate_pushdown(predicate.clone(), ctx))
+            .collect_vec();
+        self.clone_with_inputs(&new_inputs)
+    }
+}
+
+impl ToBatch for LogicalIntersect {
+    fn to_batch(&self) -> Result<PlanRef> {
+        self.lower_to_union_and_agg()?.to_batch()
+    }
+}
+
+impl ToStream for LogicalIntersect {
+    fn to_stream(&self, ctx: &mut ToStreamContext) -> Result<PlanRef> {
+        self.lower_to_union_and_agg()?.to_stream(ctx)
+    }
+
+    fn logical_rewrite_for_stream(
+        &self,
+        ctx: &mut RewriteStreamContext,
+    ) -> Result<(PlanRef, ColIndexMapping)> {
+        let original_schema_len = self.base.schema().len();
+        // Normalize every input back onto the original schema's column layout via its *own*
+        // col_change, mirroring `LogicalUnion::logical_rewrite_for_stream`: keeping only the
+        // first input's col_change (as before) would silently misalign inputs 1..n whenever
+        // their rewrite differs from input 0's (virtually guaranteed once row-id/pk columns get
+        // synthesized per-input).
+        let new_inputs = self
+            .core
+            .inputs
+            .iter()
+            .map(|input| {
+                let (new_input, col_change) = input.logical_rewrite_for_stream(ctx)?;
+                let exprs = (0..original_schema_len)
+                    .map(|x| {
+                        let new_pos = col_change.map(x);
+                        ExprImpl::InputRef(
+                            InputRef::new(
+                                new_pos,
+                                new_input.schema().fields[new_pos].data_type.clone(),
+                            )
+                            .into(),
+                        )
+                    })
+                    .collect_vec();
+                Ok(LogicalProject::create(new_input, exprs))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let new_intersect = Self::new(self.all(), new_inputs);
+        // Every input was already re-projected onto `original_schema_len` positions above, so the
+        // output mapping is identity.
+        let out_col_change =
+            ColIndexMapping::identity_or_none(original_schema_len, new_intersect.schema().len());
+        Ok((new_intersect.into(), out_col_change))
+    }
+}
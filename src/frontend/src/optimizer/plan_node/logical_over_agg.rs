@@ -23,13 +23,14 @@ use risingwave_expr::function::window::{Frame, FrameBound, WindowFuncKind};
 
 use super::generic::{OverWindow, PlanWindowFunction};
 use super::{
-    gen_filter_and_pushdown, ColPrunable, ExprRewritable, LogicalProject, PlanBase, PlanRef,
-    PlanTreeNodeUnary, PredicatePushdown, ToBatch, ToStream,
+    gen_filter_and_pushdown, BatchOverWindow, ColPrunable, ExprRewritable, LogicalProject,
+    PlanBase, PlanRef, PlanTreeNodeUnary, PredicatePushdown, StreamOverWindow, ToBatch, ToStream,
 };
 use crate::expr::{Expr, ExprImpl, InputRef, WindowFunction};
 use crate::optimizer::plan_node::{
     ColumnPruningContext, PredicatePushdownContext, RewriteStreamContext, ToStreamContext,
 };
+use crate::optimizer::property::RequiredDist;
 use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalOverAgg` performs `OVER` window aggregates ([`WindowFunction`]) to its input.
@@ -73,21 +74,14 @@ impl LogicalOverAgg {
             }
         }
         for f in &window_funcs {
-            if f.kind.is_rank() {
-                if f.order_by.sort_exprs.is_empty() {
-                    return Err(ErrorCode::InvalidInputSyntax(format!(
-                        "window rank function without order by: {:?}",
-                        f
-                    ))
-                    .into());
-                }
-                if f.kind == WindowFuncKind::DenseRank {
-                    return Err(ErrorCode::NotImplemented(
-                        format!("window rank function: {}", f.kind),
-                        4847.into(),
-                    )
-                    .into());
-                }
+            if (f.kind.is_rank() || f.kind.is_partition_distribution())
+                && f.order_by.sort_exprs.is_empty()
+            {
+                return Err(ErrorCode::InvalidInputSyntax(format!(
+                    "window rank function without order by: {:?}",
+                    f
+                ))
+                .into());
             }
         }
 
@@ -103,6 +97,12 @@ impl LogicalOverAgg {
     fn convert_window_function(window_function: WindowFunction) -> Result<PlanWindowFunction> {
         // TODO: rewrite expressions in `ORDER BY`, `PARTITION BY` and arguments to `InputRef` like
         // in `LogicalAgg`
+        let order_by_types: Vec<DataType> = window_function
+            .order_by
+            .sort_exprs
+            .iter()
+            .map(|e| e.expr.return_type())
+            .collect();
         let order_by: Vec<_> = window_function
             .order_by
             .sort_exprs
@@ -128,39 +128,139 @@ impl LogicalOverAgg {
             .try_collect()?;
 
         let mut args = window_function.args;
+        match window_function.kind {
+            WindowFuncKind::FirstValue | WindowFuncKind::LastValue => {
+                if args.len() != 1 {
+                    return Err(ErrorCode::InvalidInputSyntax(format!(
+                        "`{}` takes exactly one argument",
+                        window_function.kind
+                    ))
+                    .into());
+                }
+            }
+            WindowFuncKind::NthValue => {
+                if args.len() != 2 {
+                    return Err(ErrorCode::InvalidInputSyntax(format!(
+                        "`{}` takes exactly two arguments: value and n",
+                        window_function.kind
+                    ))
+                    .into());
+                }
+                if !args[1].return_type().is_int() {
+                    return Err(ErrorCode::InvalidInputSyntax(format!(
+                        "the `n` of `{}` function should be integer",
+                        window_function.kind
+                    ))
+                    .into());
+                }
+            }
+            _ => {}
+        }
+
         let frame = match window_function.kind {
-            WindowFuncKind::RowNumber | WindowFuncKind::Rank | WindowFuncKind::DenseRank => {
-                // ignore frame for rank functions
+            WindowFuncKind::RowNumber
+            | WindowFuncKind::Rank
+            | WindowFuncKind::DenseRank
+            | WindowFuncKind::CumeDist
+            | WindowFuncKind::PercentRank => {
+                // rank and distribution functions are computed over the whole partition, not a
+                // sub-frame
+                None
+            }
+            WindowFuncKind::NTile => {
+                if args.len() != 1 {
+                    return Err(ErrorCode::InvalidInputSyntax(
+                        "`NTILE` takes exactly one argument: bucket count".to_string(),
+                    )
+                    .into());
+                }
+                if !args[0].return_type().is_int() {
+                    return Err(ErrorCode::InvalidInputSyntax(
+                        "the bucket count of `NTILE` should be integer".to_string(),
+                    )
+                    .into());
+                }
+                let bucket_count = args[0]
+                    .clone()
+                    .cast_implicit(DataType::Int64)?
+                    .eval_row_const()?
+                    .map(|v| *v.as_int64());
+                if !matches!(bucket_count, Some(n) if n > 0) {
+                    return Err(ErrorCode::InvalidInputSyntax(
+                        "the bucket count of `NTILE` must be a positive constant".to_string(),
+                    )
+                    .into());
+                }
+                // `PlanWindowFunction` has no field for the bucket count, so it stays in `args`;
+                // being a constant rather than an `InputRef`, it is rejected by the argument
+                // conversion below, and planning `NTILE` fails with `NotImplemented`.
                 None
             }
             WindowFuncKind::Lag | WindowFuncKind::Lead => {
-                let offset = if args.len() > 1 {
-                    let offset_expr = args.remove(1);
-                    if !offset_expr.return_type().is_int() {
+                if args.len() > 2 {
+                    return Err(ErrorCode::InvalidInputSyntax(format!(
+                        "`{}` takes at most two arguments: value and offset",
+                        window_function.kind
+                    ))
+                    .into());
+                }
+                if args.len() == 2 {
+                    if !args[1].return_type().is_int() {
                         return Err(ErrorCode::InvalidInputSyntax(format!(
                             "the `offset` of `{}` function should be integer",
                             window_function.kind
                         ))
                         .into());
                     }
-                    offset_expr
-                        .cast_implicit(DataType::Int64)?
-                        .eval_row_const()?
-                        .map(|v| *v.as_int64() as usize)
-                        .unwrap_or(1usize)
+                    if args[1].is_const() {
+                        // constant offset: fold it into a fixed `ROWS` frame so the executor
+                        // doesn't need to re-evaluate it on every row.
+                        let offset_expr = args.remove(1);
+                        let offset = offset_expr
+                            .cast_implicit(DataType::Int64)?
+                            .eval_row_const()?
+                            .map(|v| *v.as_int64() as usize)
+                            .unwrap_or(1usize);
+                        Some(if window_function.kind == WindowFuncKind::Lag {
+                            Frame::Rows(FrameBound::Preceding(offset), FrameBound::CurrentRow)
+                        } else {
+                            Frame::Rows(FrameBound::CurrentRow, FrameBound::Following(offset))
+                        })
+                    } else {
+                        // dynamic offset, e.g. `LAG(x, some_column)`: keep it as the function's
+                        // second argument instead of folding it into a fixed frame, and let the
+                        // executor re-evaluate it per row.
+                        None
+                    }
                 } else {
-                    1usize
-                };
-
-                // override the frame
-                // TODO(rc): We can only do the optimization for constant offset.
-                Some(if window_function.kind == WindowFuncKind::Lag {
-                    Frame::Rows(FrameBound::Preceding(offset), FrameBound::CurrentRow)
+                    Some(if window_function.kind == WindowFuncKind::Lag {
+                        Frame::Rows(FrameBound::Preceding(1), FrameBound::CurrentRow)
+                    } else {
+                        Frame::Rows(FrameBound::CurrentRow, FrameBound::Following(1))
+                    })
+                }
+            }
+            kind if kind.is_value_function() => {
+                // `FIRST_VALUE`/`LAST_VALUE`/`NTH_VALUE` resolve against the frame. Per standard
+                // SQL, the implicit default frame is `RANGE BETWEEN UNBOUNDED PRECEDING AND
+                // CURRENT ROW` when there's an `ORDER BY`, but the whole partition
+                // (`UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING`) when there isn't one - a
+                // `CURRENT ROW` bound only means something relative to an ordering.
+                let frame = window_function.frame.or(Some(if order_by_types.is_empty() {
+                    Frame::Range(FrameBound::UnboundedPreceding, FrameBound::UnboundedFollowing)
                 } else {
-                    Frame::Rows(FrameBound::CurrentRow, FrameBound::Following(offset))
-                })
+                    Frame::Range(FrameBound::UnboundedPreceding, FrameBound::CurrentRow)
+                }));
+                // Validate the frame actually used (post-default), not the pre-default
+                // `window_function.frame`, so a synthesized default is checked same as an
+                // explicit one.
+                validate_range_frame(frame.as_ref(), &order_by_types)?;
+                frame
+            }
+            _ => {
+                validate_range_frame(window_function.frame.as_ref(), &order_by_types)?;
+                window_function.frame
             }
-            _ => window_function.frame,
         };
 
         let args = args
@@ -189,6 +289,57 @@ impl LogicalOverAgg {
     }
 }
 
+/// Checks that a `RANGE` frame, if present, has exactly one `ORDER BY` column and that its type
+/// supports offset arithmetic; `ROWS`/`GROUPS` frames and the absence of a frame are always fine.
+///
+/// A `RANGE BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING` frame is exempt from the
+/// `ORDER BY` requirement: both bounds are unbounded, so there's no offset to evaluate against an
+/// ordering column, and this is exactly the frame `FIRST_VALUE`/`LAST_VALUE`/`NTH_VALUE` default
+/// to when there's no `ORDER BY` at all.
+fn validate_range_frame(frame: Option<&Frame>, order_by_types: &[DataType]) -> Result<()> {
+    if let Some(Frame::Range(start, end)) = frame {
+        if matches!(start, FrameBound::UnboundedPreceding)
+            && matches!(end, FrameBound::UnboundedFollowing)
+        {
+            return Ok(());
+        }
+        let [order_col_type] = order_by_types else {
+            return Err(ErrorCode::InvalidInputSyntax(
+                "RANGE frame requires exactly one ORDER BY column".to_string(),
+            )
+            .into());
+        };
+        if !is_range_offset_supported(order_col_type) {
+            return Err(ErrorCode::NotImplemented(
+                format!(
+                    "RANGE frame offset for ORDER BY column of type {}",
+                    order_col_type
+                ),
+                None.into(),
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Whether a `RANGE` frame offset can be added to / subtracted from `order_col_type` (e.g. a
+/// numeric offset against a numeric column, or an interval offset against a timestamp column).
+fn is_range_offset_supported(order_col_type: &DataType) -> bool {
+    matches!(
+        order_col_type,
+        DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Decimal
+            | DataType::Date
+            | DataType::Timestamp
+            | DataType::Timestamptz
+    )
+}
+
 impl PlanTreeNodeUnary for LogicalOverAgg {
     fn input(&self) -> PlanRef {
         self.core.input.clone()
@@ -209,13 +360,94 @@ impl fmt::Display for LogicalOverAgg {
 
 impl ColPrunable for LogicalOverAgg {
     fn prune_col(&self, required_cols: &[usize], ctx: &mut ColumnPruningContext) -> PlanRef {
-        let mapping = ColIndexMapping::with_remaining_columns(required_cols, self.schema().len());
-        let new_input = {
-            let input = self.input();
-            let required = (0..input.schema().len()).collect_vec(); // TODO(rc): real pruning
-            input.prune_col(&required, ctx)
-        };
-        LogicalProject::with_mapping(self.clone_with_input(new_input).into(), mapping).into()
+        let input_len = self.core.input.schema().len();
+        let window_functions = &self.core.window_functions;
+
+        // Window functions (by index into `window_functions`) whose output column is actually
+        // required; a window function that isn't required contributes nothing to the backward
+        // liveness set below.
+        let mut required_func_indices = required_cols
+            .iter()
+            .filter(|&&idx| idx >= input_len)
+            .map(|&idx| idx - input_len)
+            .collect_vec();
+        required_func_indices.sort_unstable();
+        required_func_indices.dedup();
+
+        // Backward liveness over the input: start from the directly required input columns,
+        // then add every `InputRef` referenced by a surviving window function's args,
+        // `PARTITION BY` and `ORDER BY`.
+        let mut required_input_cols = FixedBitSet::with_capacity(input_len);
+        for &idx in required_cols {
+            if idx < input_len {
+                required_input_cols.insert(idx);
+            }
+        }
+        for &i in &required_func_indices {
+            let f = &window_functions[i];
+            for arg in &f.args {
+                required_input_cols.insert(arg.index());
+            }
+            for col in &f.partition_by {
+                required_input_cols.insert(col.index());
+            }
+            for order in &f.order_by {
+                required_input_cols.insert(order.column_index);
+            }
+        }
+        let required_input_cols = required_input_cols.ones().collect_vec();
+
+        let input_change = ColIndexMapping::with_remaining_columns(&required_input_cols, input_len);
+        let new_input = self.input().prune_col(&required_input_cols, ctx);
+
+        let new_window_functions = required_func_indices
+            .iter()
+            .map(|&i| {
+                let mut f = window_functions[i].clone();
+                f.args = f
+                    .args
+                    .iter()
+                    .map(|arg| InputRef::new(input_change.map(arg.index()), arg.data_type.clone()))
+                    .collect();
+                f.partition_by = f
+                    .partition_by
+                    .iter()
+                    .map(|col| InputRef::new(input_change.map(col.index()), col.data_type.clone()))
+                    .collect();
+                f.order_by = f
+                    .order_by
+                    .iter()
+                    .map(|order| {
+                        ColumnOrder::new(input_change.map(order.column_index), order.order_type)
+                    })
+                    .collect();
+                f
+            })
+            .collect_vec();
+
+        let new_over_agg: PlanRef = Self::new(new_window_functions, new_input).into();
+
+        // Old output index (over the original `self.schema()`) -> new output index (over
+        // `new_over_agg`'s schema), for the columns we kept.
+        let mut old_to_new = vec![None; self.schema().len()];
+        for (new_idx, &old_idx) in required_input_cols.iter().enumerate() {
+            old_to_new[old_idx] = Some(new_idx);
+        }
+        let num_kept_input_cols = required_input_cols.len();
+        for (new_idx, &old_func_idx) in required_func_indices.iter().enumerate() {
+            old_to_new[input_len + old_func_idx] = Some(num_kept_input_cols + new_idx);
+        }
+
+        let mapping = ColIndexMapping::new(
+            required_cols.iter().map(|&idx| old_to_new[idx]).collect_vec(),
+            new_over_agg.schema().len(),
+        );
+
+        if mapping.is_identity() {
+            new_over_agg
+        } else {
+            LogicalProject::with_mapping(new_over_agg, mapping).into()
+        }
     }
 }
 
@@ -236,19 +468,80 @@ impl PredicatePushdown for LogicalOverAgg {
 
 impl ToBatch for LogicalOverAgg {
     fn to_batch(&self) -> Result<PlanRef> {
-        Err(ErrorCode::NotImplemented("OverAgg to batch".to_string(), 9124.into()).into())
+        let new_input = self.input().to_batch()?;
+        let new_logical = self.clone_with_input(new_input);
+        // `BatchOverWindow` requires its input sorted by `partition_by` then `order_by`; satisfy
+        // that with an existing sort if the input already provides it, or insert one otherwise.
+        let required_order = BatchOverWindow::sort_order(&new_logical.core);
+        let sorted_input = required_order.enforce_if_not_satisfies(new_logical.core.input)?;
+        Ok(BatchOverWindow::new(OverWindow::new(
+            new_logical.core.window_functions,
+            sorted_input,
+        ))?
+        .into())
     }
 }
 
 impl ToStream for LogicalOverAgg {
-    fn to_stream(&self, _ctx: &mut ToStreamContext) -> Result<PlanRef> {
-        Err(ErrorCode::NotImplemented("OverAgg to stream".to_string(), 9124.into()).into())
+    fn to_stream(&self, ctx: &mut ToStreamContext) -> Result<PlanRef> {
+        let partition_key_indices = self
+            .core
+            .window_functions
+            .iter()
+            .flat_map(|f| f.partition_by.iter().map(|i| i.index()))
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let dist = if partition_key_indices.is_empty() {
+            RequiredDist::single()
+        } else {
+            RequiredDist::hash_shard(&partition_key_indices)
+        };
+        let new_input = self.input().to_stream_with_dist_required(&dist, ctx)?;
+        let new_logical = self.clone_with_input(new_input);
+        Ok(StreamOverWindow::new(new_logical.core).into())
     }
 
     fn logical_rewrite_for_stream(
         &self,
-        _ctx: &mut RewriteStreamContext,
+        ctx: &mut RewriteStreamContext,
     ) -> Result<(PlanRef, ColIndexMapping)> {
-        Err(ErrorCode::NotImplemented("OverAgg to stream".to_string(), 9124.into()).into())
+        let (input, col_change) = self.input().logical_rewrite_for_stream(ctx)?;
+        // The rewritten input's columns may have been appended to or reordered (e.g. a
+        // synthesized pk), so every `InputRef` inside each window function's `args`/
+        // `partition_by`/`order_by` must be remapped through `col_change`, the same way
+        // `ColPrunable::prune_col` above does for `input_change`.
+        let new_window_functions = self
+            .core
+            .window_functions
+            .iter()
+            .map(|f| {
+                let mut f = f.clone();
+                f.args = f
+                    .args
+                    .iter()
+                    .map(|arg| InputRef::new(col_change.map(arg.index()), arg.data_type.clone()))
+                    .collect();
+                f.partition_by = f
+                    .partition_by
+                    .iter()
+                    .map(|col| InputRef::new(col_change.map(col.index()), col.data_type.clone()))
+                    .collect();
+                f.order_by = f
+                    .order_by
+                    .iter()
+                    .map(|order| {
+                        ColumnOrder::new(col_change.map(order.column_index), order.order_type)
+                    })
+                    .collect();
+                f
+            })
+            .collect_vec();
+        let new_logical = Self::new(new_window_functions, input);
+        let out_col_change = ColIndexMapping::identity_or_none(
+            col_change.target_size(),
+            new_logical.schema().len(),
+        );
+        Ok((new_logical.into(), out_col_change))
     }
 }
@@ -145,7 +145,14 @@ impl ToBatch for LogicalUnion {
 
 impl ToStream for LogicalUnion {
     fn to_stream(&self, ctx: &mut ToStreamContext) -> Result<PlanRef> {
-        // TODO: use round robin distribution instead of using hash distribution of all inputs.
+        // NOT IMPLEMENTED: round-robin redistribution for streaming UNION ALL was requested
+        // (avoid the hash reshuffle below for append-only branches that don't need co-location)
+        // but is not delivered in this checkout and should not be treated as done. It would need
+        // `RequiredDist` to grow a round-robin mode and `StreamUnion`'s dispatcher to track a
+        // per-actor cursor instead of hashing keys; neither `RequiredDist`'s definition nor the
+        // dispatcher live anywhere in this source tree, so there's nothing safe to extend here.
+        // Until that infrastructure exists, every input is still forced through a hash shuffle on
+        // the output pk so it's at least correct, if not optimally cheap.
         let dist = RequiredDist::hash_shard(self.base.logical_pk());
         let new_inputs: Result<Vec<_>> = self
             .inputs()
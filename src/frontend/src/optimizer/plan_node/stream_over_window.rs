@@ -0,0 +1,101 @@
+// Copyright 2023 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_pb::stream_plan::stream_node::PbNodeBody;
+use risingwave_pb::stream_plan::OverWindowNode;
+
+use super::generic::{OverWindow, PlanWindowFunction};
+use super::{generic, ExprRewritable, PlanBase, PlanRef, PlanTreeNodeUnary, StreamNode};
+use crate::stream_fragmenter::BuildFragmentGraphState;
+
+/// `StreamOverWindow` computes window functions over ordered, partitioned state tables.
+///
+/// Each partition key gets its own slice of a single state table, keyed additionally by the
+/// `order_by` [`risingwave_common::util::sort_util::ColumnOrder`]s so the rows of a partition can
+/// be scanned in the order the window frames are defined over. On every input change, only the
+/// rows whose frame overlaps the changed row are recomputed, and the corresponding
+/// update/delete/insert chunks are emitted for the rest of the stream graph.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StreamOverWindow {
+    pub base: PlanBase,
+    core: OverWindow<PlanRef>,
+}
+
+impl StreamOverWindow {
+    pub fn new(core: OverWindow<PlanRef>) -> Self {
+        let base = PlanBase::new_stream_with_core(
+            &core,
+            core.input.distribution().clone(),
+            core.input.append_only(),
+            core.input.watermark_columns().clone(),
+        );
+        Self { base, core }
+    }
+
+    pub fn window_functions(&self) -> &[PlanWindowFunction] {
+        &self.core.window_functions
+    }
+}
+
+impl PlanTreeNodeUnary for StreamOverWindow {
+    fn input(&self) -> PlanRef {
+        self.core.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(OverWindow::new(self.core.window_functions.clone(), input))
+    }
+}
+
+impl_plan_tree_node_for_unary! { StreamOverWindow }
+
+impl fmt::Display for StreamOverWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.core.fmt_with_name(f, "StreamOverWindow")
+    }
+}
+
+impl ExprRewritable for StreamOverWindow {}
+
+impl StreamNode for StreamOverWindow {
+    fn to_stream_prost_body(&self, state: &mut BuildFragmentGraphState) -> PbNodeBody {
+        let partition_by = self
+            .core
+            .window_functions
+            .iter()
+            .flat_map(|f| f.partition_by.iter().map(|i| i.index() as u32))
+            .unique()
+            .collect_vec();
+
+        // A single state table, keyed by partition key and then the window functions' `order_by`
+        // columns so the frame for a changed row can be recomputed by scanning its neighbours.
+        let state_table = generic::OverWindow::infer_state_table(&self.core)
+            .with_id(state.gen_table_id_wrapped())
+            .to_internal_table_prost();
+
+        PbNodeBody::OverWindow(OverWindowNode {
+            partition_by,
+            calls: self
+                .core
+                .window_functions
+                .iter()
+                .map(|f| f.to_protobuf())
+                .collect(),
+            state_table: Some(state_table),
+        })
+    }
+}
@@ -105,10 +105,20 @@ pub struct MetaMetrics {
 
     /// The number of workers in the cluster.
     pub worker_num: IntGaugeVec,
+    /// Disk / object-store capacity reported by each worker, labeled by `worker_addr`,
+    /// `partition` (`data` / `meta`) and `kind` (`available` / `total`).
+    pub worker_storage_bytes: IntGaugeVec,
     pub compact_skip_frequency: IntCounterVec,
 
     /// The roles of all meta nodes in the cluster.
     pub meta_type: IntGaugeVec,
+    /// Seconds since each worker's last heartbeat, labeled by `worker_addr`/`worker_type`.
+    pub worker_last_seen_seconds: IntGaugeVec,
+    /// Whether each worker is currently considered live (1) or not (0), labeled by
+    /// `worker_addr`/`worker_type`.
+    pub worker_up: IntGaugeVec,
+    /// Incremented whenever the meta leader changes, as observed from `election_client`.
+    pub meta_leader_transitions_total: IntCounterVec,
 
     /// compaction
     pub compact_pending_bytes: IntGaugeVec,
@@ -343,6 +353,14 @@ impl MetaMetrics {
             registry,
         )
         .unwrap();
+        let worker_storage_bytes = register_int_gauge_vec_with_registry!(
+            "worker_storage_bytes",
+            "disk/object-store capacity reported by each worker",
+            &["worker_addr", "partition", "kind"],
+            registry,
+        )
+        .unwrap();
+
         let scale_compactor_core_num = register_int_gauge_with_registry!(
             "storage_compactor_suggest_core_count",
             "num of CPU to be scale to meet compaction need",
@@ -358,6 +376,30 @@ impl MetaMetrics {
         )
         .unwrap();
 
+        let worker_last_seen_seconds = register_int_gauge_vec_with_registry!(
+            "worker_last_seen_seconds",
+            "seconds since each worker's last heartbeat",
+            &["worker_addr", "worker_type"],
+            registry,
+        )
+        .unwrap();
+
+        let worker_up = register_int_gauge_vec_with_registry!(
+            "worker_up",
+            "whether each worker is currently considered live",
+            &["worker_addr", "worker_type"],
+            registry,
+        )
+        .unwrap();
+
+        let meta_leader_transitions_total = register_int_counter_vec_with_registry!(
+            "meta_leader_transitions_total",
+            "number of times the meta leader has changed",
+            &["new_leader_id"],
+            registry,
+        )
+        .unwrap();
+
         let compact_pending_bytes = register_int_gauge_vec_with_registry!(
             "storage_compact_pending_bytes",
             "bytes of lsm tree needed to reach balance",
@@ -419,7 +461,11 @@ impl MetaMetrics {
             hummock_manager_real_process_time,
             time_after_last_observation: AtomicU64::new(0),
             worker_num,
+            worker_storage_bytes,
             meta_type,
+            worker_last_seen_seconds,
+            worker_up,
+            meta_leader_transitions_total,
             compact_pending_bytes,
             compact_level_compression_ratio,
             scale_compactor_core_num,
@@ -431,6 +477,26 @@ impl MetaMetrics {
     pub fn registry(&self) -> &Registry {
         &self.registry
     }
+
+    /// Like [`MetaMetrics::new`], but additionally spawns a background task that periodically
+    /// reads every family off `registry` and hands it to the OTLP push for `endpoint`, for
+    /// deployments that run a push-based collector and cannot scrape the meta node directly.
+    ///
+    /// This only adds a push sink; every gauge/histogram/counter above is still defined exactly
+    /// once against `self.registry`; scraping it directly continues to work unchanged.
+    ///
+    /// The push itself is not implemented yet (see [`crate::rpc::otlp`]): calling this spawns a
+    /// task that logs a warning on every tick instead of actually exporting anything. Don't call
+    /// this expecting a working OTLP sink.
+    pub fn with_otlp(endpoint: String, push_interval: Duration) -> Self {
+        let this = Self::new();
+        tokio::spawn(crate::rpc::otlp::start_push_loop(
+            this.registry.clone(),
+            endpoint,
+            push_interval,
+        ));
+        this
+    }
 }
 impl Default for MetaMetrics {
     fn default() -> Self {
@@ -448,6 +514,7 @@ pub async fn start_worker_info_monitor<S: MetaStore>(
     let join_handle = tokio::spawn(async move {
         let mut monitor_interval = tokio::time::interval(interval);
         monitor_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut prev_leader_id: Option<String> = None;
         loop {
             tokio::select! {
                 // Wait for interval
@@ -465,11 +532,30 @@ pub async fn start_worker_info_monitor<S: MetaStore>(
                     .with_label_values(&[(worker_type.as_str_name())])
                     .set(worker_num as i64);
             }
+            // TODO: `ClusterManager` doesn't yet expose a per-worker resource-stat fetch to pair
+            // with the `count_worker_node` tally above (the small RPC addition this table needs).
+            // Leave `worker_storage_bytes` registered but unset until that lands, rather than
+            // calling a method that doesn't exist.
+            // TODO: `ClusterManager` doesn't yet expose per-worker last-heartbeat timestamps, so
+            // there's no way to compute `lastSeenSecsAgo`/`isUp` here. Leave
+            // `worker_last_seen_seconds`/`worker_up` registered but unset until that RPC exists,
+            // rather than calling a method that was never implemented.
             if let Some(client) = &election_client && let Ok(meta_members) = client.get_members().await {
                 meta_metrics
                     .worker_num
                     .with_label_values(&[WorkerType::Meta.as_str_name()])
                     .set(meta_members.len() as i64);
+                let leader_id = meta_members.iter().find(|m| m.is_leader).map(|m| m.id.clone());
+                if let Some(new_leader_id) = &leader_id
+                    && prev_leader_id.is_some()
+                    && prev_leader_id.as_ref() != Some(new_leader_id)
+                {
+                    meta_metrics
+                        .meta_leader_transitions_total
+                        .with_label_values(&[new_leader_id])
+                        .inc();
+                }
+                prev_leader_id = leader_id;
                 meta_members.into_iter().for_each(|m| {
                     let role = if m.is_leader {"leader"} else {"follower"};
                     meta_metrics.meta_type.with_label_values(&[&m.id, role]).set(1);
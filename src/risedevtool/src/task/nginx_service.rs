@@ -13,13 +13,26 @@
 // limitations under the License.
 
 use std::env;
+use std::fmt::Write as _;
+use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{anyhow, Result};
 
-use crate::{ExecuteContext, NginxConfig, Task};
+use crate::{ExecuteContext, Task};
+
+/// Config for a single `nginx` service instance: what to listen on and how to route to the rest
+/// of the cluster.
+#[derive(Clone, Debug)]
+pub struct NginxConfig {
+    pub id: String,
+    pub address: String,
+    pub port: u16,
+    pub upstreams: Vec<NginxUpstreamGroup>,
+    pub routes: Vec<NginxRoute>,
+}
 
 pub struct NginxService {
     pub config: NginxConfig,
@@ -35,9 +48,96 @@ impl NginxService {
         Ok(Path::new(&prefix_bin).join("nginx"))
     }
 
-    fn redis(&self) -> Result<Command> {
+    fn nginx_binary(&self) -> Result<Command> {
         Ok(Command::new(self.nginx_path()?))
     }
+
+    /// Path of the `nginx.conf` rendered for this service, under `PREFIX_CONFIG`.
+    fn config_path(&self) -> Result<PathBuf> {
+        let prefix_config = env::var("PREFIX_CONFIG")?;
+        Ok(Path::new(&prefix_config).join(format!("{}-nginx.conf", self.config.id)))
+    }
+
+    /// Renders the `upstream` block for a single group, including a passive health-check style
+    /// `max_fails`/`fail_timeout` pair on each server so a downed node is taken out of rotation.
+    fn render_upstream(out: &mut String, upstream: &NginxUpstreamGroup) {
+        writeln!(out, "    upstream {} {{", upstream.name).unwrap();
+        for server in &upstream.servers {
+            writeln!(out, "        server {} max_fails=3 fail_timeout=5s;", server).unwrap();
+        }
+        writeln!(out, "    }}").unwrap();
+    }
+
+    /// Renders the `location` blocks that route a prefix to its upstream group.
+    fn render_route(out: &mut String, route: &NginxRoute) {
+        writeln!(out, "        location {} {{", route.path_prefix).unwrap();
+        writeln!(out, "            proxy_pass http://{};", route.upstream).unwrap();
+        writeln!(out, "        }}").unwrap();
+    }
+
+    /// Renders a full `nginx.conf` fronting the configured upstream groups with the configured
+    /// route prefixes, listening on `self.config.address:self.config.port`.
+    fn render_config(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "worker_processes auto;").unwrap();
+        writeln!(out, "events {{ worker_connections 1024; }}").unwrap();
+        writeln!(out, "http {{").unwrap();
+        for upstream in &self.config.upstreams {
+            Self::render_upstream(&mut out, upstream);
+        }
+        writeln!(out, "    server {{").unwrap();
+        writeln!(
+            out,
+            "        listen {}:{};",
+            self.config.address, self.config.port
+        )
+        .unwrap();
+        for route in &self.config.routes {
+            Self::render_route(&mut out, route);
+        }
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+        out
+    }
+
+    fn write_config(&self) -> Result<PathBuf> {
+        let path = self.config_path()?;
+        let mut file = File::create(&path)?;
+        file.write_all(self.render_config().as_bytes())?;
+        Ok(path)
+    }
+
+    /// Re-renders `nginx.conf` in place and asks the running master process to reload it via
+    /// `nginx -s reload`, so picking up an upstream change doesn't drop in-flight connections.
+    pub fn reload(&self) -> Result<()> {
+        let config_path = self.write_config()?;
+        let status = self
+            .nginx_binary()?
+            .arg("-c")
+            .arg(&config_path)
+            .arg("-s")
+            .arg("reload")
+            .status()?;
+        if !status.success() {
+            return Err(anyhow!("nginx -s reload failed with status {:?}", status));
+        }
+        Ok(())
+    }
+}
+
+/// A named group of upstream servers (`host:port`) that route rules can proxy to, e.g. the
+/// frontend nodes or the compute nodes of a local cluster.
+#[derive(Clone, Debug)]
+pub struct NginxUpstreamGroup {
+    pub name: String,
+    pub servers: Vec<String>,
+}
+
+/// A route rule mapping a path prefix to an upstream group, e.g. `/` -> `frontend`.
+#[derive(Clone, Debug)]
+pub struct NginxRoute {
+    pub path_prefix: String,
+    pub upstream: String,
 }
 
 impl Task for NginxService {
@@ -46,16 +146,16 @@ impl Task for NginxService {
         ctx.pb.set_message("starting");
         let path = self.nginx_path()?;
         if !path.exists() {
-            return Err(anyhow!("Nginx binary not found in {:?}\nDid you enable redis feature in `./risedev configure`?", path));
+            return Err(anyhow!(
+                "Nginx binary not found in {:?}\nDid you enable the nginx feature in `./risedev configure`?",
+                path
+            ));
         }
 
-        let mut cmd = self.redis()?;
-        cmd.arg("--bind")
-            .arg(&self.config.address)
-            .arg("--port")
-            .arg(self.config.port.to_string())
-            .arg("--shutdown-on-sigint")
-            .arg("nosave");
+        let config_path = self.write_config()?;
+
+        let mut cmd = self.nginx_binary()?;
+        cmd.arg("-c").arg(&config_path);
 
         ctx.run_command(ctx.tmux_run(cmd)?)?;
         ctx.pb.set_message("started");